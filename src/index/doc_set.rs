@@ -0,0 +1,263 @@
+/// Result of seeking a [`DocSet`] to a target document id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipResult {
+    /// The cursor landed exactly on the requested id.
+    Reached,
+    /// The cursor landed past the requested id (the set doesn't contain it).
+    OverStep,
+    /// The set is exhausted; there is no id at or past the target.
+    End,
+}
+
+/// A forward-only cursor over a sorted set of document ids.
+///
+/// Invariant: `advance` must be called once before `doc` is valid, and
+/// `seek` always moves the cursor strictly forward, never backward.
+pub trait DocSet {
+    /// Move to the next document id, if any.
+    fn advance(&mut self) -> Option<usize>;
+
+    /// Move forward to the first document id `>= target`.
+    fn seek(&mut self, target: usize) -> SkipResult;
+
+    /// The document id the cursor currently sits on, or `None` before the
+    /// first `advance`/`seek` call or once the set is exhausted.
+    fn doc(&self) -> Option<usize>;
+}
+
+/// A [`DocSet`] cursor over a sorted slice of document ids (a
+/// `PostingList`'s `doc_ids`). `seek` uses galloping (exponential) search:
+/// it probes offsets `1, 2, 4, 8, ...` ahead of the cursor until it
+/// brackets the target, then binary-searches within that bracket.
+pub struct PostingCursor<'p> {
+    doc_ids: &'p [usize],
+    // `None` means "before the first element".
+    idx: Option<usize>,
+}
+
+impl<'p> PostingCursor<'p> {
+    pub fn new(doc_ids: &'p [usize]) -> Self {
+        Self { doc_ids, idx: None }
+    }
+}
+
+impl DocSet for PostingCursor<'_> {
+    fn advance(&mut self) -> Option<usize> {
+        let next = self.idx.map_or(0, |i| i + 1);
+        if next >= self.doc_ids.len() {
+            self.idx = Some(self.doc_ids.len());
+            return None;
+        }
+        self.idx = Some(next);
+        Some(self.doc_ids[next])
+    }
+
+    fn seek(&mut self, target: usize) -> SkipResult {
+        let start = self.idx.map_or(0, |i| i);
+        if start >= self.doc_ids.len() {
+            return SkipResult::End;
+        }
+        if self.doc_ids[start] >= target {
+            self.idx = Some(start);
+            return if self.doc_ids[start] == target {
+                SkipResult::Reached
+            } else {
+                SkipResult::OverStep
+            };
+        }
+
+        // Gallop outward from `start` until the probe brackets `target`.
+        let mut lo = start;
+        let mut step = 1;
+        let hi;
+        loop {
+            let probe = start + step;
+            if probe >= self.doc_ids.len() {
+                hi = self.doc_ids.len() - 1;
+                break;
+            }
+            if self.doc_ids[probe] >= target {
+                hi = probe;
+                break;
+            }
+            lo = probe;
+            step *= 2;
+        }
+
+        // Binary search the bracket [lo, hi] for the first id >= target.
+        let bracket = &self.doc_ids[lo..=hi];
+        let offset = bracket.partition_point(|&id| id < target);
+        let found = lo + offset;
+
+        if found >= self.doc_ids.len() {
+            self.idx = Some(self.doc_ids.len());
+            return SkipResult::End;
+        }
+
+        self.idx = Some(found);
+        if self.doc_ids[found] == target {
+            SkipResult::Reached
+        } else {
+            SkipResult::OverStep
+        }
+    }
+
+    fn doc(&self) -> Option<usize> {
+        match self.idx {
+            Some(i) if i < self.doc_ids.len() => Some(self.doc_ids[i]),
+            _ => None,
+        }
+    }
+}
+
+/// A [`DocSet`] over the conjunction of several child sets: it yields only
+/// document ids present in every child. Rather than enumerating each
+/// child's full id list, it repeatedly seeks every child to the current
+/// maximum doc id until they all agree, so a rare term paired with a
+/// common one costs `O(r * log(c))` rather than a full linear merge.
+pub struct Intersection<D> {
+    children: Vec<D>,
+    current: Option<usize>,
+}
+
+impl<D: DocSet> Intersection<D> {
+    pub fn new(children: Vec<D>) -> Self {
+        Self {
+            children,
+            current: None,
+        }
+    }
+
+    /// Seek every child forward, starting from `target`, until they all
+    /// land on the same doc id.
+    fn align(&mut self, mut target: usize) -> Option<usize> {
+        if self.children.is_empty() {
+            return None;
+        }
+        'outer: loop {
+            for child in &mut self.children {
+                match child.seek(target) {
+                    SkipResult::Reached => continue,
+                    SkipResult::OverStep => {
+                        target = child.doc()?;
+                        continue 'outer;
+                    }
+                    SkipResult::End => return None,
+                }
+            }
+            self.current = Some(target);
+            return Some(target);
+        }
+    }
+}
+
+impl<D: DocSet> DocSet for Intersection<D> {
+    fn advance(&mut self) -> Option<usize> {
+        let next = self.children.first_mut()?.advance()?;
+        self.align(next)
+    }
+
+    fn seek(&mut self, target: usize) -> SkipResult {
+        match self.align(target) {
+            Some(doc_id) if doc_id == target => SkipResult::Reached,
+            Some(_) => SkipResult::OverStep,
+            None => SkipResult::End,
+        }
+    }
+
+    fn doc(&self) -> Option<usize> {
+        self.current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn posting_cursor_advance_walks_every_id() {
+        let ids = vec![1, 4, 9];
+        let mut cursor = PostingCursor::new(&ids);
+        assert_eq!(cursor.doc(), None);
+        assert_eq!(cursor.advance(), Some(1));
+        assert_eq!(cursor.advance(), Some(4));
+        assert_eq!(cursor.advance(), Some(9));
+        assert_eq!(cursor.advance(), None);
+    }
+
+    #[test]
+    fn posting_cursor_seek_lands_on_exact_match() {
+        let ids = vec![1, 4, 9, 16, 25];
+        let mut cursor = PostingCursor::new(&ids);
+        assert_eq!(cursor.seek(9), SkipResult::Reached);
+        assert_eq!(cursor.doc(), Some(9));
+    }
+
+    #[test]
+    fn posting_cursor_seek_overshoots_missing_target() {
+        let ids = vec![1, 4, 9, 16, 25];
+        let mut cursor = PostingCursor::new(&ids);
+        assert_eq!(cursor.seek(10), SkipResult::OverStep);
+        assert_eq!(cursor.doc(), Some(16));
+    }
+
+    #[test]
+    fn posting_cursor_seek_past_end_returns_end() {
+        let ids = vec![1, 4, 9];
+        let mut cursor = PostingCursor::new(&ids);
+        assert_eq!(cursor.seek(100), SkipResult::End);
+        assert_eq!(cursor.doc(), None);
+    }
+
+    #[test]
+    fn posting_cursor_seek_never_moves_backward() {
+        let ids: Vec<usize> = (0..100).collect();
+        let mut cursor = PostingCursor::new(&ids);
+        assert_eq!(cursor.seek(50), SkipResult::Reached);
+        assert_eq!(cursor.seek(60), SkipResult::Reached);
+        // seeking to an earlier id just holds position, never rewinds.
+        assert_eq!(cursor.seek(10), SkipResult::OverStep);
+        assert_eq!(cursor.doc(), Some(60));
+    }
+
+    #[test]
+    fn intersection_yields_only_common_ids() {
+        let a = vec![1, 2, 3, 8, 10];
+        let b = vec![2, 3, 4, 10];
+        let c = vec![2, 3, 10, 20];
+
+        let mut intersection = Intersection::new(vec![
+            PostingCursor::new(&a),
+            PostingCursor::new(&b),
+            PostingCursor::new(&c),
+        ]);
+
+        let mut hits = Vec::new();
+        while let Some(doc_id) = intersection.advance() {
+            hits.push(doc_id);
+        }
+
+        assert_eq!(hits, vec![2, 3, 10]);
+    }
+
+    #[test]
+    fn intersection_of_rare_and_common_term_finds_shared_doc() {
+        let rare = vec![42];
+        let common: Vec<usize> = (0..1000).collect();
+
+        let mut intersection =
+            Intersection::new(vec![PostingCursor::new(&rare), PostingCursor::new(&common)]);
+
+        assert_eq!(intersection.advance(), Some(42));
+        assert_eq!(intersection.advance(), None);
+    }
+
+    #[test]
+    fn intersection_with_no_overlap_is_empty() {
+        let a = vec![1, 2, 3];
+        let b = vec![4, 5, 6];
+
+        let mut intersection = Intersection::new(vec![PostingCursor::new(&a), PostingCursor::new(&b)]);
+        assert_eq!(intersection.advance(), None);
+    }
+}
@@ -1,6 +1,69 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::doc_set::{DocSet, Intersection, PostingCursor};
+use super::query;
+use super::varint;
+
 type DocID = usize;
 
+/// A pluggable token normalizer (e.g. a stemmer or ASCII-folder).
+type Normalizer = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Runtime-configurable indexing behavior: which tokens to drop and how to
+/// normalize the rest, plus which named fields an `IndexRequest` is allowed
+/// to index.
+#[derive(Clone, Default)]
+pub struct IndexSettings {
+    pub stop_words: HashSet<String>,
+    pub searchable_attributes: Vec<String>,
+    normalizer: Option<Normalizer>,
+}
+
+impl IndexSettings {
+    /// Install a normalizer (e.g. a stemmer or ASCII-folder) run on every
+    /// token before the stop-word check.
+    pub fn with_normalizer(mut self, normalizer: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        self.normalizer = Some(Arc::new(normalizer));
+        self
+    }
+
+    fn normalize(&self, token: &str) -> String {
+        match &self.normalizer {
+            Some(normalizer) => normalizer(token),
+            None => token.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Debug for IndexSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IndexSettings")
+            .field("stop_words", &self.stop_words)
+            .field("searchable_attributes", &self.searchable_attributes)
+            .field("normalizer", &self.normalizer.as_ref().map(|_| "Fn(&str) -> String"))
+            .finish()
+    }
+}
+
+/// How document scores are computed during `rank`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScoringMode {
+    /// Classic `tf * idf` with `tf = term_count / doc_len` and `idf = log10(N / n_t)`.
+    TfIdf,
+    /// Okapi BM25, with term-frequency saturation (`k1`) and length normalization (`b`).
+    Bm25 { k1: f64, b: f64 },
+}
+
+impl Default for ScoringMode {
+    fn default() -> Self {
+        ScoringMode::Bm25 { k1: 1.2, b: 0.75 }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PostingList {
     // Sorted document ids
@@ -23,6 +86,10 @@ impl PostingList {
             positions,
         }
     }
+
+    fn cursor(&self) -> PostingCursor<'_> {
+        PostingCursor::new(&self.doc_ids)
+    }
 }
 
 impl Default for PostingList {
@@ -35,19 +102,183 @@ impl Default for PostingList {
 pub struct InvertedIndex {
     postings: HashMap<String, PostingList>,
     doc_lengths: HashMap<DocID, usize>,
+    scoring_mode: ScoringMode,
+    // Running sum of doc_lengths, kept in lockstep with doc_lengths so avgdl is O(1).
+    total_doc_length: usize,
+    avgdl: f64,
+    settings: IndexSettings,
 }
 
 impl InvertedIndex {
     pub fn new(postings: HashMap<String, PostingList>, doc_lengths: HashMap<DocID, usize>) -> Self {
+        let total_doc_length: usize = doc_lengths.values().sum();
+        let avgdl = if doc_lengths.is_empty() {
+            0.0
+        } else {
+            total_doc_length as f64 / doc_lengths.len() as f64
+        };
+
         Self {
             postings,
             doc_lengths,
+            scoring_mode: ScoringMode::default(),
+            total_doc_length,
+            avgdl,
+            settings: IndexSettings::default(),
+        }
+    }
+
+    /// Builder-style setter for the scoring mode used by `rank`.
+    pub fn with_scoring_mode(mut self, scoring_mode: ScoringMode) -> Self {
+        self.scoring_mode = scoring_mode;
+        self
+    }
+
+    /// Builder-style setter for the stop-word/normalizer/searchable-attribute
+    /// settings used by `tokenize`.
+    pub fn with_settings(mut self, settings: IndexSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Update the stop-word list used by `tokenize` at runtime.
+    pub fn set_stop_words(&mut self, stop_words: HashSet<String>) {
+        self.settings.stop_words = stop_words;
+    }
+
+    /// Update the searchable-attribute allowlist used when indexing
+    /// field-based documents at runtime.
+    pub fn set_searchable_attributes(&mut self, searchable_attributes: Vec<String>) {
+        self.settings.searchable_attributes = searchable_attributes;
+    }
+
+    pub fn searchable_attributes(&self) -> &[String] {
+        &self.settings.searchable_attributes
+    }
+
+    /// Join a field-based document's values, name-sorted for determinism,
+    /// into the single block of text `tokenize` expects. When
+    /// `searchable_attributes` is non-empty, fields not in it are dropped;
+    /// an empty allowlist (the default) indexes every field.
+    pub fn filter_searchable_fields(&self, fields: &HashMap<String, String>) -> String {
+        let searchable = &self.settings.searchable_attributes;
+        let mut names: Vec<&String> = fields.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .filter(|name| searchable.is_empty() || searchable.contains(name))
+            .map(|name| fields[name].as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Install a normalizer used by `tokenize` at runtime (e.g. a stemmer or
+    /// ASCII-folder), without disturbing the stop words/searchable
+    /// attributes already configured.
+    pub fn set_normalizer(&mut self, normalizer: impl Fn(&str) -> String + Send + Sync + 'static) {
+        self.settings = std::mem::take(&mut self.settings).with_normalizer(normalizer);
+    }
+
+    /// Lowercase and normalize a single query-side term, the same way
+    /// `tokenize` treats an index-side token, so lookups against `postings`
+    /// agree on both sides.
+    fn normalize_term(&self, term: &str) -> String {
+        self.settings.normalize(&term.to_lowercase())
+    }
+
+    /// Rewrite a parsed query tree so its terms agree with how `tokenize`
+    /// built the vocabulary: every term/phrase word is run through the
+    /// configured normalizer, and any term that's a configured stop word is
+    /// dropped from its surrounding `And`/`Or` group entirely (it never got
+    /// a posting at index time, so requiring it to match would make the
+    /// whole group match nothing). Stop words inside a `Phrase` are left in
+    /// place here -- `eval` drops them itself via `phrase_terms_with_gaps`,
+    /// which also needs to know the gap each one leaves behind.
+    fn normalize_query(&self, op: query::Operation) -> query::Operation {
+        match op {
+            query::Operation::Term(term) => query::Operation::Term(self.normalize_term(&term)),
+            query::Operation::Phrase(words) => {
+                query::Operation::Phrase(words.iter().map(|w| self.normalize_term(w)).collect())
+            }
+            query::Operation::And(operands) => query::Operation::And(
+                operands
+                    .into_iter()
+                    .map(|op| self.normalize_query(op))
+                    .filter(|op| !self.is_stop_term(op))
+                    .collect(),
+            ),
+            query::Operation::Or(operands) => query::Operation::Or(
+                operands
+                    .into_iter()
+                    .map(|op| self.normalize_query(op))
+                    .filter(|op| !self.is_stop_term(op))
+                    .collect(),
+            ),
         }
     }
 
+    fn is_stop_term(&self, op: &query::Operation) -> bool {
+        matches!(op, query::Operation::Term(term) if self.settings.stop_words.contains(term))
+    }
+
+    /// Split `text` on whitespace into `(token, position)` pairs, lowercasing
+    /// and normalizing each token and dropping stop words. A stop word still
+    /// consumes a position, so phrase offsets across the surrounding tokens
+    /// stay correct.
+    pub fn tokenize(&self, text: &str) -> Vec<(String, usize)> {
+        text.split_whitespace()
+            .enumerate()
+            .filter_map(|(pos, raw)| {
+                let token = self.settings.normalize(&raw.to_lowercase());
+                if self.settings.stop_words.contains(&token) {
+                    None
+                } else {
+                    Some((token, pos))
+                }
+            })
+            .collect()
+    }
+
+    fn recompute_avgdl(&mut self) {
+        self.avgdl = if self.doc_lengths.is_empty() {
+            0.0
+        } else {
+            self.total_doc_length as f64 / self.doc_lengths.len() as f64
+        };
+    }
+
+    /// Remove `doc_id` from every posting list and from `doc_lengths`,
+    /// pruning any posting whose list becomes empty as a result. A no-op if
+    /// `doc_id` isn't indexed.
+    pub fn delete_document(&mut self, doc_id: DocID) {
+        let Some(old_len) = self.doc_lengths.remove(&doc_id) else {
+            return;
+        };
+        self.total_doc_length -= old_len;
+        self.recompute_avgdl();
+
+        self.postings.retain(|_, posting| {
+            if let Ok(idx) = posting.doc_ids.binary_search(&doc_id) {
+                posting.doc_ids.remove(idx);
+                posting.term_frequencies.remove(idx);
+                posting.positions.remove(idx);
+            }
+            !posting.doc_ids.is_empty()
+        });
+    }
+
+    /// Index `tokens` under `doc_id`. If `doc_id` is already indexed, it's
+    /// deleted first, so re-indexing a document replaces its previous
+    /// contents instead of accumulating on top of them.
     pub fn add_document(&mut self, doc_id: DocID, tokens: Vec<(String, usize)>) {
+        if self.doc_lengths.contains_key(&doc_id) {
+            self.delete_document(doc_id);
+        }
+
         let num_tokens = tokens.len();
         self.doc_lengths.insert(doc_id, num_tokens);
+        self.total_doc_length += num_tokens;
+        self.recompute_avgdl();
         for (token, pos) in tokens {
             match self.postings.get_mut(&token) {
                 None => {
@@ -72,34 +303,532 @@ impl InvertedIndex {
         }
     }
 
-    pub fn rank(&self, q: &str) -> Option<Vec<DocID>> {
-        let posting = self.postings.get(q)?;
+    /// Persist the index under `dir` as four files: `postings.bin` (each
+    /// posting list's doc ids and positions delta-gap encoded, then
+    /// varint-packed), `vocab.bin` (each term plus its byte offset and
+    /// length into `postings.bin`, so a single term can be loaded without
+    /// reading the rest of the file), `doc_lengths.bin`, and `settings.bin`
+    /// (scoring mode, stop words, and searchable attributes, so a restart
+    /// doesn't silently revert a live `set_stop_words`/`set_searchable_attributes`
+    /// call). The normalizer is a closure and can't be serialized, so it is
+    /// NOT persisted -- reinstall one via `set_normalizer` after loading.
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+
+        // Sorted so re-saving an unchanged index produces identical bytes.
+        let mut terms: Vec<&String> = self.postings.keys().collect();
+        terms.sort();
+
+        let mut postings_buf = Vec::new();
+        let mut vocab_buf = Vec::new();
+        for term in terms {
+            let posting = &self.postings[term];
+            let offset = postings_buf.len() as u64;
+            encode_posting(posting, &mut postings_buf);
+            let length = postings_buf.len() as u64 - offset;
+
+            varint::write(&mut vocab_buf, term.len() as u64);
+            vocab_buf.extend_from_slice(term.as_bytes());
+            varint::write(&mut vocab_buf, offset);
+            varint::write(&mut vocab_buf, length);
+        }
+
+        let mut doc_lengths_buf = Vec::new();
+        varint::write(&mut doc_lengths_buf, self.doc_lengths.len() as u64);
+        for (&doc_id, &len) in &self.doc_lengths {
+            varint::write(&mut doc_lengths_buf, doc_id as u64);
+            varint::write(&mut doc_lengths_buf, len as u64);
+        }
+
+        let mut settings_buf = Vec::new();
+        encode_scoring_mode(self.scoring_mode, &mut settings_buf);
+
+        let mut stop_words: Vec<&String> = self.settings.stop_words.iter().collect();
+        stop_words.sort();
+        varint::write(&mut settings_buf, stop_words.len() as u64);
+        for word in stop_words {
+            varint::write(&mut settings_buf, word.len() as u64);
+            settings_buf.extend_from_slice(word.as_bytes());
+        }
+
+        varint::write(&mut settings_buf, self.settings.searchable_attributes.len() as u64);
+        for attribute in &self.settings.searchable_attributes {
+            varint::write(&mut settings_buf, attribute.len() as u64);
+            settings_buf.extend_from_slice(attribute.as_bytes());
+        }
+
+        fs::write(dir.join("postings.bin"), postings_buf)?;
+        fs::write(dir.join("vocab.bin"), vocab_buf)?;
+        fs::write(dir.join("doc_lengths.bin"), doc_lengths_buf)?;
+        fs::write(dir.join("settings.bin"), settings_buf)?;
+        Ok(())
+    }
+
+    /// Load an index previously written by [`InvertedIndex::save`].
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let postings_bytes = fs::read(dir.join("postings.bin"))?;
+        let vocab_bytes = fs::read(dir.join("vocab.bin"))?;
+        let doc_lengths_bytes = fs::read(dir.join("doc_lengths.bin"))?;
+        let settings_bytes = fs::read(dir.join("settings.bin"))?;
+
+        let mut postings = HashMap::new();
+        let mut pos = 0;
+        while pos < vocab_bytes.len() {
+            let term_len = varint::read(&vocab_bytes, &mut pos) as usize;
+            let term = String::from_utf8_lossy(&vocab_bytes[pos..pos + term_len]).into_owned();
+            pos += term_len;
+            let offset = varint::read(&vocab_bytes, &mut pos) as usize;
+            let length = varint::read(&vocab_bytes, &mut pos) as usize;
+            let posting = decode_posting(&postings_bytes[offset..offset + length]);
+            postings.insert(term, posting);
+        }
+
+        let mut doc_lengths = HashMap::new();
+        let mut doc_lengths_pos = 0;
+        let doc_count = varint::read(&doc_lengths_bytes, &mut doc_lengths_pos);
+        for _ in 0..doc_count {
+            let doc_id = varint::read(&doc_lengths_bytes, &mut doc_lengths_pos) as DocID;
+            let len = varint::read(&doc_lengths_bytes, &mut doc_lengths_pos) as usize;
+            doc_lengths.insert(doc_id, len);
+        }
+
+        let mut settings_pos = 0;
+        let scoring_mode = decode_scoring_mode(&settings_bytes, &mut settings_pos);
+
+        let stop_word_count = varint::read(&settings_bytes, &mut settings_pos);
+        let mut stop_words = HashSet::with_capacity(stop_word_count as usize);
+        for _ in 0..stop_word_count {
+            let len = varint::read(&settings_bytes, &mut settings_pos) as usize;
+            let word = String::from_utf8_lossy(&settings_bytes[settings_pos..settings_pos + len]).into_owned();
+            settings_pos += len;
+            stop_words.insert(word);
+        }
+
+        let searchable_attribute_count = varint::read(&settings_bytes, &mut settings_pos);
+        let mut searchable_attributes = Vec::with_capacity(searchable_attribute_count as usize);
+        for _ in 0..searchable_attribute_count {
+            let len = varint::read(&settings_bytes, &mut settings_pos) as usize;
+            let attribute =
+                String::from_utf8_lossy(&settings_bytes[settings_pos..settings_pos + len]).into_owned();
+            settings_pos += len;
+            searchable_attributes.push(attribute);
+        }
+
+        Ok(Self::new(postings, doc_lengths)
+            .with_scoring_mode(scoring_mode)
+            .with_settings(IndexSettings {
+                stop_words,
+                searchable_attributes,
+                normalizer: None,
+            }))
+    }
+
+    /// Score a single document's occurrence within `posting` under the
+    /// index's current `scoring_mode`. Returns `None` if the document has
+    /// no recorded length or isn't present in `posting`.
+    fn term_score(&self, posting: &PostingList, doc_id: DocID) -> Option<f64> {
+        let idx = posting.doc_ids.binary_search(&doc_id).ok()?;
+        let doc_term_n = posting.term_frequencies[idx];
+        let doc_len = *self.doc_lengths.get(&doc_id)?;
+        if doc_len == 0 {
+            return None;
+        }
+
         let n = self.doc_lengths.len() as f64;
         let n_t = posting.doc_ids.len() as f64;
-        let idf = (n / n_t).log(10.0);
-        let mut doc_tf_idf: Vec<(DocID, f64)> = posting
+
+        let score = match self.scoring_mode {
+            ScoringMode::TfIdf => {
+                let idf = (n / n_t).log(10.0);
+                let tf = (doc_term_n as f64) / (doc_len as f64);
+                tf * idf
+            }
+            ScoringMode::Bm25 { k1, b } => {
+                let idf = ((n - n_t + 0.5) / (n_t + 0.5) + 1.0).ln();
+                let tf = doc_term_n as f64;
+                let norm = 1.0 - b + b * (doc_len as f64) / self.avgdl;
+                idf * (tf * (k1 + 1.0)) / (tf + k1 * norm)
+            }
+        };
+
+        Some(score)
+    }
+
+    /// Rank documents containing `q`, highest-scoring first. `q` is
+    /// lowercased and run through the configured normalizer before lookup,
+    /// the same as a token is at index time, so e.g. a stemming normalizer
+    /// that turns "cats" into "cat" at index time also applies here.
+    pub fn rank(&self, q: &str) -> Option<Vec<DocID>> {
+        let term = self.normalize_term(q);
+        let posting = self.postings.get(&term)?;
+
+        let mut doc_scores: Vec<(DocID, f64)> = posting
             .doc_ids
             .iter()
-            .zip(posting.term_frequencies.iter())
-            .filter_map(|(doc_id, doc_term_n)| {
-                let doc_len = *self.doc_lengths.get(doc_id)?;
-                if doc_len == 0 {
-                    return None;
+            .filter_map(|doc_id| Some((*doc_id, self.term_score(posting, *doc_id)?)))
+            .collect();
+
+        doc_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let res = doc_scores.iter().map(|(doc_id, _)| *doc_id).collect();
+        Some(res)
+    }
+
+    /// Typo-tolerant ranking: scores documents under `term` and under any
+    /// vocabulary term within `max_distance` edits, OR-merging their
+    /// posting lists. Edited matches are discounted relative to exact ones
+    /// so an exact match always outranks a typo-corrected one. When
+    /// `prefix` is set, a vocabulary term matching `term` as a literal
+    /// prefix counts as an exact (distance-0) match, for as-you-type search.
+    /// `term` is normalized the same way as `rank`, so it agrees with how
+    /// the vocabulary it's compared against was built.
+    pub fn rank_fuzzy(&self, term: &str, max_distance: u8, prefix: bool) -> Vec<DocID> {
+        let term = self.normalize_term(term);
+        let max_distance = max_distance as usize;
+        let term_len = term.chars().count();
+
+        let mut doc_scores: HashMap<DocID, f64> = HashMap::new();
+        for (candidate, posting) in &self.postings {
+            let distance = if prefix && candidate.starts_with(term.as_str()) {
+                Some(0)
+            } else {
+                let candidate_len = candidate.chars().count();
+                if candidate_len.abs_diff(term_len) > max_distance {
+                    None
+                } else {
+                    levenshtein_within(&term, candidate, max_distance)
                 }
+            };
+
+            let Some(distance) = distance else { continue };
+            // Each additional edit halves the contribution, so exact
+            // matches (distance 0) always outrank edited ones.
+            let penalty = 1.0 / (1.0 + distance as f64);
+
+            for doc_id in &posting.doc_ids {
+                if let Some(score) = self.term_score(posting, *doc_id) {
+                    *doc_scores.entry(*doc_id).or_insert(0.0) += score * penalty;
+                }
+            }
+        }
+
+        let mut ranked: Vec<(DocID, f64)> = doc_scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        ranked.into_iter().map(|(doc_id, _)| doc_id).collect()
+    }
+
+    /// Score every document matching `doc_id` under `term`, keyed by doc id.
+    fn term_scores(&self, term: &str) -> HashMap<DocID, f64> {
+        let Some(posting) = self.postings.get(term) else {
+            return HashMap::new();
+        };
+        posting
+            .doc_ids
+            .iter()
+            .filter_map(|doc_id| Some((*doc_id, self.term_score(posting, *doc_id)?)))
+            .collect()
+    }
+
+    /// Drop stop words out of a parsed phrase's word list, pairing each
+    /// remaining word with how many stop words were elided directly before
+    /// it (0 for an ordinarily-adjacent pair). `phrase_scores` uses this to
+    /// require a wider position gap across an elided stop word instead of
+    /// the usual 1, since `tokenize` still reserves a position for a
+    /// dropped stop word.
+    ///
+    /// This only accounts for stop words typed in the query itself -- if a
+    /// *document* has a stop word between two phrase terms that the query
+    /// doesn't mention (e.g. phrase `"rust search"` against the indexed
+    /// text "rust the search"), the phrase still won't match, since nothing
+    /// at search time records which document positions were stop words.
+    fn phrase_terms_with_gaps(&self, words: &[String]) -> Vec<(String, usize)> {
+        let mut terms = Vec::new();
+        let mut elided = 0;
+        for word in words {
+            if self.settings.stop_words.contains(word) {
+                elided += 1;
+            } else {
+                terms.push((word.clone(), elided));
+                elided = 0;
+            }
+        }
+        terms
+    }
+
+    /// Score every document matching a consecutive run of `terms`, keyed by
+    /// doc id. A document matches only if, for every term i in the phrase,
+    /// there is an occurrence at position `p` such that term i+1 occurs at
+    /// position `p + 1 + gap` in the same document, where `gap` is the
+    /// number of stop words elided between the two in the query (see
+    /// `phrase_terms_with_gaps`).
+    fn phrase_scores(&self, terms: &[(String, usize)]) -> HashMap<DocID, f64> {
+        let Some(postings) = terms
+            .iter()
+            .map(|(word, _)| self.postings.get(word))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return HashMap::new();
+        };
+        if postings.is_empty() {
+            return HashMap::new();
+        }
+
+        // Cumulative offset from the phrase's first surviving term: term i
+        // normally sits i positions after the first, but each stop word
+        // elided between term i-1 and term i widens that gap by one.
+        let mut offsets = Vec::with_capacity(terms.len());
+        let mut offset = 0;
+        for (i, &(_, gap)) in terms.iter().enumerate() {
+            if i > 0 {
+                offset += 1 + gap;
+            }
+            offsets.push(offset);
+        }
+
+        let mut intersection = Intersection::new(postings.iter().map(|p| p.cursor()).collect());
+        let mut candidates = Vec::new();
+        while let Some(doc_id) = intersection.advance() {
+            candidates.push(doc_id);
+        }
+
+        candidates
+            .into_iter()
+            .filter_map(|doc_id| {
+                let position_lists: Vec<&Vec<usize>> = postings
+                    .iter()
+                    .map(|posting| {
+                        let idx = posting.doc_ids.binary_search(&doc_id).unwrap();
+                        &posting.positions[idx]
+                    })
+                    .collect();
 
-                let tf = (*doc_term_n as f64) / (doc_len as f64);
+                let is_consecutive = position_lists[0].iter().any(|&start| {
+                    position_lists[1..]
+                        .iter()
+                        .zip(offsets[1..].iter())
+                        .all(|(positions, &offset)| positions.contains(&(start + offset)))
+                });
+                if !is_consecutive {
+                    return None;
+                }
 
-                Some((*doc_id, tf * idf))
+                let score: f64 = postings
+                    .iter()
+                    .filter_map(|posting| self.term_score(posting, doc_id))
+                    .sum();
+                Some((doc_id, score))
             })
-            .collect();
+            .collect()
+    }
 
-        doc_tf_idf.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    /// Score every document containing all of `terms`, by intersecting
+    /// their posting lists with galloping seeks rather than enumerating
+    /// every candidate.
+    fn and_term_scores(&self, terms: &[&str]) -> HashMap<DocID, f64> {
+        let Some(postings) = terms
+            .iter()
+            .map(|t| self.postings.get(*t))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return HashMap::new();
+        };
+        if postings.is_empty() {
+            return HashMap::new();
+        }
 
-        let res = doc_tf_idf.iter().map(|(doc_id, _)| *doc_id).collect();
-        Some(res)
+        let mut intersection = Intersection::new(postings.iter().map(|p| p.cursor()).collect());
+        let mut result = HashMap::new();
+        while let Some(doc_id) = intersection.advance() {
+            let score: f64 = postings
+                .iter()
+                .filter_map(|posting| self.term_score(posting, doc_id))
+                .sum();
+            result.insert(doc_id, score);
+        }
+        result
+    }
+
+    /// Evaluate a parsed `Operation` tree, returning each matching document's
+    /// summed per-term score.
+    fn eval(&self, op: &query::Operation) -> HashMap<DocID, f64> {
+        match op {
+            query::Operation::Term(term) => self.term_scores(term),
+            query::Operation::Phrase(words) => {
+                self.phrase_scores(&self.phrase_terms_with_gaps(words))
+            }
+            query::Operation::And(operands) => match and_operand_terms(operands) {
+                // Fast path: every operand is a plain term, so we can
+                // intersect their posting lists directly with `Intersection`
+                // instead of materializing each operand's full score map.
+                Some(terms) => self.and_term_scores(&terms),
+                None => operands
+                    .iter()
+                    .map(|operand| self.eval(operand))
+                    .fold(None, |acc: Option<HashMap<DocID, f64>>, scores| match acc {
+                        None => Some(scores),
+                        Some(acc) => Some(
+                            acc.into_iter()
+                                .filter_map(|(doc_id, score)| {
+                                    scores.get(&doc_id).map(|other| (doc_id, score + other))
+                                })
+                                .collect(),
+                        ),
+                    })
+                    .unwrap_or_default(),
+            },
+            query::Operation::Or(operands) => {
+                let mut combined: HashMap<DocID, f64> = HashMap::new();
+                for operand in operands {
+                    for (doc_id, score) in self.eval(operand) {
+                        *combined.entry(doc_id).or_insert(0.0) += score;
+                    }
+                }
+                combined
+            }
+        }
+    }
+
+    /// Run a multi-term boolean/phrase query (see [`query::parse`] for the
+    /// accepted syntax) and return matching documents ranked by summed
+    /// per-term score, highest first. Query terms are normalized and
+    /// stop-word-filtered the same way `tokenize` treats index-side tokens
+    /// (see `normalize_query`).
+    pub fn search(&self, q: &str) -> Vec<DocID> {
+        let op = self.normalize_query(query::parse(q));
+        let mut doc_scores: Vec<(DocID, f64)> = self.eval(&op).into_iter().collect();
+        doc_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        doc_scores.into_iter().map(|(doc_id, _)| doc_id).collect()
     }
 }
 
+/// Delta-gap and varint encode a posting list: doc ids and positions are
+/// both strictly increasing, so each is stored as the gap from the
+/// previous value rather than the raw (larger) number.
+fn encode_posting(posting: &PostingList, buf: &mut Vec<u8>) {
+    varint::write(buf, posting.doc_ids.len() as u64);
+
+    let mut prev_doc_id = 0u64;
+    for i in 0..posting.doc_ids.len() {
+        let doc_id = posting.doc_ids[i] as u64;
+        varint::write(buf, doc_id - prev_doc_id);
+        prev_doc_id = doc_id;
+
+        varint::write(buf, posting.term_frequencies[i] as u64);
+
+        let positions = &posting.positions[i];
+        varint::write(buf, positions.len() as u64);
+        let mut prev_pos = 0u64;
+        for &position in positions {
+            let position = position as u64;
+            varint::write(buf, position - prev_pos);
+            prev_pos = position;
+        }
+    }
+}
+
+/// Inverse of [`encode_posting`].
+fn decode_posting(bytes: &[u8]) -> PostingList {
+    let mut pos = 0;
+    let doc_count = varint::read(bytes, &mut pos) as usize;
+
+    let mut doc_ids = Vec::with_capacity(doc_count);
+    let mut term_frequencies = Vec::with_capacity(doc_count);
+    let mut positions = Vec::with_capacity(doc_count);
+
+    let mut prev_doc_id = 0u64;
+    for _ in 0..doc_count {
+        prev_doc_id += varint::read(bytes, &mut pos);
+        doc_ids.push(prev_doc_id as DocID);
+
+        term_frequencies.push(varint::read(bytes, &mut pos) as usize);
+
+        let position_count = varint::read(bytes, &mut pos) as usize;
+        let mut doc_positions = Vec::with_capacity(position_count);
+        let mut prev_pos = 0u64;
+        for _ in 0..position_count {
+            prev_pos += varint::read(bytes, &mut pos);
+            doc_positions.push(prev_pos as usize);
+        }
+        positions.push(doc_positions);
+    }
+
+    PostingList::new(doc_ids, term_frequencies, positions)
+}
+
+/// Encode a `ScoringMode` as a tag byte (0 = `TfIdf`, 1 = `Bm25`) followed,
+/// for `Bm25`, by `k1` and `b` as raw little-endian `f64`s (they aren't
+/// integers, so `varint` doesn't apply).
+fn encode_scoring_mode(scoring_mode: ScoringMode, buf: &mut Vec<u8>) {
+    match scoring_mode {
+        ScoringMode::TfIdf => buf.push(0),
+        ScoringMode::Bm25 { k1, b } => {
+            buf.push(1);
+            buf.extend_from_slice(&k1.to_le_bytes());
+            buf.extend_from_slice(&b.to_le_bytes());
+        }
+    }
+}
+
+/// Inverse of [`encode_scoring_mode`].
+fn decode_scoring_mode(bytes: &[u8], pos: &mut usize) -> ScoringMode {
+    let tag = bytes[*pos];
+    *pos += 1;
+    match tag {
+        0 => ScoringMode::TfIdf,
+        _ => {
+            let k1 = f64::from_le_bytes(bytes[*pos..*pos + 8].try_into().unwrap());
+            *pos += 8;
+            let b = f64::from_le_bytes(bytes[*pos..*pos + 8].try_into().unwrap());
+            *pos += 8;
+            ScoringMode::Bm25 { k1, b }
+        }
+    }
+}
+
+/// Compute the Levenshtein edit distance between `a` and `b`, bailing out
+/// early once it's provably greater than `max_distance`: after each DP row
+/// we check whether every entry already exceeds `max_distance` (distance
+/// only grows from there), so a clearly-mismatched candidate is rejected
+/// long before the full `O(len_a * len_b)` table is filled.
+fn levenshtein_within(a: &str, b: &str, max_distance: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > max_distance {
+        return None;
+    }
+
+    let mut prev_row: Vec<usize> = (0..=b.len()).collect();
+    for (i, &a_char) in a.iter().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, &b_char) in b.iter().enumerate() {
+            let cost = if a_char == b_char { 0 } else { 1 };
+            row[j + 1] = (prev_row[j + 1] + 1)
+                .min(row[j] + 1)
+                .min(prev_row[j] + cost);
+        }
+        if row.iter().min().is_some_and(|&min| min > max_distance) {
+            return None;
+        }
+        prev_row = row;
+    }
+
+    let distance = prev_row[b.len()];
+    (distance <= max_distance).then_some(distance)
+}
+
+/// If every operand of an `And` is a plain `Term`, return their term
+/// strings so the caller can take the fast posting-list-intersection path.
+fn and_operand_terms(operands: &[query::Operation]) -> Option<Vec<&str>> {
+    operands
+        .iter()
+        .map(|operand| match operand {
+            query::Operation::Term(term) => Some(term.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
 impl Default for InvertedIndex {
     fn default() -> Self {
         Self::new(HashMap::new(), HashMap::new())
@@ -221,7 +950,7 @@ mod tests {
     }
 
     #[test]
-    fn index_same_doc_multiple_calls_accumulates_correctly() {
+    fn index_same_doc_multiple_calls_replaces_instead_of_accumulating() {
         let mut index = InvertedIndex::default();
 
         index.add_document(1, vec![("foo".to_string(), 0), ("foo".to_string(), 2)]);
@@ -232,12 +961,66 @@ mod tests {
             .get("foo")
             .expect("posting for 'foo' should exist");
 
+        // the second call replaces doc 1's contents rather than appending
         assert_eq!(posting.doc_ids, vec![1]);
-        assert_eq!(posting.term_frequencies, vec![3]);
-        assert_eq!(posting.positions, vec![vec![0, 2, 5]]);
+        assert_eq!(posting.term_frequencies, vec![1]);
+        assert_eq!(posting.positions, vec![vec![5]]);
+
+        // doc_lengths reflects only the latest call's token count
+        assert_eq!(index.doc_lengths.get(&1), Some(&1));
+    }
+
+    #[test]
+    fn delete_document_removes_postings_and_length() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("rust".to_string(), 0), ("search".to_string(), 1)]);
+        index.add_document(2, vec![("rust".to_string(), 0)]);
+
+        index.delete_document(1);
+
+        assert_eq!(index.doc_lengths.get(&1), None);
+        assert_eq!(index.doc_lengths.get(&2), Some(&1));
+
+        let rust_posting = index.postings.get("rust").expect("'rust' should remain");
+        assert_eq!(rust_posting.doc_ids, vec![2]);
+
+        // 'search' only appeared in doc 1, so its posting is pruned entirely
+        assert!(!index.postings.contains_key("search"));
+    }
+
+    #[test]
+    fn delete_document_on_unknown_doc_id_is_a_no_op() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("rust".to_string(), 0)]);
+
+        index.delete_document(42);
 
-        // doc_lengths keeps the length of the *last* call for this doc_id
         assert_eq!(index.doc_lengths.get(&1), Some(&1));
+        assert_eq!(index.postings.get("rust").unwrap().doc_ids, vec![1]);
+    }
+
+    #[test]
+    fn reindexing_a_document_keeps_bm25_scoring_correct() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("rust".to_string(), 0)]);
+        index.add_document(2, vec![("rust".to_string(), 0), ("rust".to_string(), 1)]);
+
+        // replace doc 1 with a much longer document so avgdl must update too
+        index.add_document(
+            1,
+            vec![
+                ("rust".to_string(), 0),
+                ("filler".to_string(), 1),
+                ("filler".to_string(), 2),
+                ("filler".to_string(), 3),
+            ],
+        );
+
+        assert_eq!(index.doc_lengths.get(&1), Some(&4));
+        assert_eq!(index.avgdl, 3.0);
+
+        let ranked = index.rank("rust").expect("should rank 'rust'");
+        assert_eq!(ranked.len(), 2);
     }
 
     #[test]
@@ -284,6 +1067,151 @@ mod tests {
         assert_eq!(ranked, vec![1, 2]);
     }
 
+    #[test]
+    fn rank_defaults_to_bm25() {
+        let index = InvertedIndex::default();
+        assert_eq!(index.scoring_mode, ScoringMode::Bm25 { k1: 1.2, b: 0.75 });
+    }
+
+    #[test]
+    fn rank_with_explicit_tf_idf_matches_original_formula() {
+        let mut index = InvertedIndex::default().with_scoring_mode(ScoringMode::TfIdf);
+
+        index.add_document(1, vec![("rust".to_string(), 0)]);
+        index.add_document(
+            2,
+            vec![
+                ("rust".to_string(), 0),
+                ("rust".to_string(), 1),
+                ("extra".to_string(), 2),
+            ],
+        );
+        index.add_document(3, vec![("extra".to_string(), 0)]);
+
+        let ranked = index.rank("rust").expect("should rank 'rust'");
+
+        // doc 1 has tf = 1/1 = 1.0, doc 2 has tf = 2/3 < 1.0, same idf
+        assert_eq!(ranked, vec![1, 2]);
+    }
+
+    #[test]
+    fn rank_bm25_saturates_term_frequency() {
+        let mut index = InvertedIndex::default();
+
+        // doc 1: "rust" repeated many times in a long document
+        let mut tokens: Vec<(String, usize)> = (0..20).map(|i| ("rust".to_string(), i)).collect();
+        tokens.push(("filler".to_string(), 20));
+        index.add_document(1, tokens);
+
+        // doc 2: "rust" a couple of times in a short, on-topic document
+        index.add_document(
+            2,
+            vec![("rust".to_string(), 0), ("rust".to_string(), 1)],
+        );
+
+        let ranked = index.rank("rust").expect("should rank 'rust'");
+        assert_eq!(ranked.len(), 2);
+    }
+
+    #[test]
+    fn search_ands_multiple_terms() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("rust".to_string(), 0), ("search".to_string(), 1)]);
+        index.add_document(2, vec![("rust".to_string(), 0)]);
+        index.add_document(3, vec![("search".to_string(), 0)]);
+
+        let hits = index.search("rust search");
+        assert_eq!(hits, vec![1]);
+    }
+
+    #[test]
+    fn search_ors_multiple_terms() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("rust".to_string(), 0)]);
+        index.add_document(2, vec![("golang".to_string(), 0)]);
+        index.add_document(3, vec![("python".to_string(), 0)]);
+
+        let mut hits = index.search("rust OR golang");
+        hits.sort();
+        assert_eq!(hits, vec![1, 2]);
+    }
+
+    #[test]
+    fn search_matches_exact_phrase() {
+        let mut index = InvertedIndex::default();
+        // doc 1: "rust search" consecutively
+        index.add_document(1, vec![("rust".to_string(), 0), ("search".to_string(), 1)]);
+        // doc 2: both words present, but not adjacent
+        index.add_document(
+            2,
+            vec![
+                ("rust".to_string(), 0),
+                ("is".to_string(), 1),
+                ("search".to_string(), 2),
+            ],
+        );
+
+        let hits = index.search("\"rust search\"");
+        assert_eq!(hits, vec![1]);
+    }
+
+    #[test]
+    fn search_returns_empty_for_unknown_term() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("rust".to_string(), 0)]);
+
+        assert!(index.search("unknown").is_empty());
+    }
+
+    #[test]
+    fn rank_fuzzy_finds_single_typo() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("search".to_string(), 0)]);
+
+        let hits = index.rank_fuzzy("serach", 2, false);
+        assert_eq!(hits, vec![1]);
+    }
+
+    #[test]
+    fn rank_fuzzy_ranks_exact_match_above_typo() {
+        let mut index = InvertedIndex::default();
+        // doc 1 only has the typo'd spelling
+        index.add_document(1, vec![("serach".to_string(), 0)]);
+        // doc 2 has the exact term
+        index.add_document(2, vec![("search".to_string(), 0)]);
+
+        let hits = index.rank_fuzzy("search", 2, false);
+        assert_eq!(hits, vec![2, 1]);
+    }
+
+    #[test]
+    fn rank_fuzzy_respects_max_distance() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("search".to_string(), 0)]);
+
+        assert!(index.rank_fuzzy("zzzzzz", 2, false).is_empty());
+    }
+
+    #[test]
+    fn rank_fuzzy_prefix_matches_as_exact() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("searching".to_string(), 0)]);
+
+        let hits = index.rank_fuzzy("sear", 1, true);
+        assert_eq!(hits, vec![1]);
+    }
+
+    #[test]
+    fn levenshtein_within_computes_exact_distance() {
+        assert_eq!(levenshtein_within("search", "serach", 5), Some(2));
+        assert_eq!(levenshtein_within("search", "search", 5), Some(0));
+    }
+
+    #[test]
+    fn levenshtein_within_bails_out_past_cutoff() {
+        assert_eq!(levenshtein_within("search", "zzzzzz", 2), None);
+    }
+
     #[test]
     fn rank_skips_docs_without_length_info() {
         // Build an index where postings exist, but doc_lengths is empty.
@@ -300,4 +1228,218 @@ mod tests {
         let ranked = index.rank("term").expect("rank should return Some");
         assert!(ranked.is_empty());
     }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("inverted_index_test_{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn save_and_load_round_trips_postings_and_scores() {
+        let mut index = InvertedIndex::default();
+        index.add_document(1, vec![("rust".to_string(), 0), ("search".to_string(), 1)]);
+        index.add_document(
+            2,
+            vec![
+                ("rust".to_string(), 0),
+                ("rust".to_string(), 1),
+                ("extra".to_string(), 2),
+            ],
+        );
+
+        let dir = temp_dir("round_trip");
+        index.save(&dir).expect("save should succeed");
+        let loaded = InvertedIndex::load(&dir).expect("load should succeed");
+
+        assert_eq!(loaded.rank("rust"), index.rank("rust"));
+        assert_eq!(loaded.search("\"rust search\""), index.search("\"rust search\""));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn save_and_load_round_trips_scoring_mode_and_settings() {
+        let mut settings = IndexSettings::default();
+        settings.stop_words.insert("the".to_string());
+        let mut index = InvertedIndex::default()
+            .with_scoring_mode(ScoringMode::TfIdf)
+            .with_settings(settings);
+        index.set_searchable_attributes(vec!["title".to_string()]);
+        index.add_document(1, vec![("rust".to_string(), 0)]);
+
+        let dir = temp_dir("settings_round_trip");
+        index.save(&dir).expect("save should succeed");
+        let loaded = InvertedIndex::load(&dir).expect("load should succeed");
+
+        assert_eq!(loaded.scoring_mode, ScoringMode::TfIdf);
+        assert!(loaded.settings.stop_words.contains("the"));
+        assert_eq!(loaded.searchable_attributes(), &["title".to_string()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_missing_directory_errors() {
+        let dir = temp_dir("missing");
+        assert!(InvertedIndex::load(&dir).is_err());
+    }
+
+    #[test]
+    fn tokenize_lowercases_and_positions_every_word() {
+        let index = InvertedIndex::default();
+        assert_eq!(
+            index.tokenize("Rust Search"),
+            vec![("rust".to_string(), 0), ("search".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn tokenize_drops_stop_words_but_keeps_positions_in_sync() {
+        let mut settings = IndexSettings::default();
+        settings.stop_words.insert("the".to_string());
+        let index = InvertedIndex::default().with_settings(settings);
+
+        // "the" at position 1 is dropped, but "rust" still reports position 2
+        assert_eq!(
+            index.tokenize("search the rust"),
+            vec![("search".to_string(), 0), ("rust".to_string(), 2)]
+        );
+    }
+
+    #[test]
+    fn tokenize_applies_normalizer() {
+        let settings = IndexSettings::default().with_normalizer(|token| token.trim_end_matches('s').to_string());
+        let index = InvertedIndex::default().with_settings(settings);
+
+        assert_eq!(index.tokenize("rusts"), vec![("rust".to_string(), 0)]);
+    }
+
+    #[test]
+    fn set_stop_words_updates_tokenize_at_runtime() {
+        let mut index = InvertedIndex::default();
+        assert_eq!(index.tokenize("the rust"), vec![("the".to_string(), 0), ("rust".to_string(), 1)]);
+
+        index.set_stop_words(["the".to_string()].into_iter().collect());
+        assert_eq!(index.tokenize("the rust"), vec![("rust".to_string(), 1)]);
+    }
+
+    #[test]
+    fn search_drops_stop_words_from_query_terms() {
+        let mut settings = IndexSettings::default();
+        settings.stop_words.insert("the".to_string());
+        let mut index = InvertedIndex::default().with_settings(settings);
+
+        index.add_document(1, index.tokenize("the cat sat"));
+
+        // "the" is a stop word, so the query's effective constraint is just
+        // "cat" -- it shouldn't require a (nonexistent) posting for "the".
+        assert_eq!(index.search("the cat"), vec![1]);
+    }
+
+    #[test]
+    fn phrase_search_matches_across_a_stop_word_present_in_the_query() {
+        let mut settings = IndexSettings::default();
+        settings.stop_words.insert("the".to_string());
+        let mut index = InvertedIndex::default().with_settings(settings);
+
+        index.add_document(1, index.tokenize("i saw the cat"));
+
+        // "the" never got a posting, and it still occupies a position slot
+        // in the document, so the phrase must match across that gap.
+        assert_eq!(index.search("\"saw the cat\""), vec![1]);
+    }
+
+    #[test]
+    fn phrase_search_does_not_match_across_a_stop_word_absent_from_the_query() {
+        let mut settings = IndexSettings::default();
+        settings.stop_words.insert("the".to_string());
+        let mut index = InvertedIndex::default().with_settings(settings);
+
+        // Known limitation: the document has a stop word between "search"
+        // and "rust", but the query phrase doesn't mention it, so there's
+        // nothing at search time telling us to widen the gap.
+        index.add_document(1, index.tokenize("search the rust"));
+
+        assert!(index.search("\"search rust\"").is_empty());
+    }
+
+    #[test]
+    fn search_and_rank_apply_the_normalizer_to_query_terms() {
+        let settings =
+            IndexSettings::default().with_normalizer(|token| token.trim_end_matches('s').to_string());
+        let mut index = InvertedIndex::default().with_settings(settings);
+
+        index.add_document(1, index.tokenize("cats"));
+
+        assert_eq!(index.search("cats"), vec![1]);
+        assert_eq!(index.rank("cats"), Some(vec![1]));
+    }
+
+    #[test]
+    fn rank_fuzzy_applies_the_normalizer_to_the_query_term() {
+        let settings =
+            IndexSettings::default().with_normalizer(|token| token.trim_end_matches('s').to_string());
+        let mut index = InvertedIndex::default().with_settings(settings);
+
+        index.add_document(1, index.tokenize("cats"));
+
+        assert_eq!(index.rank_fuzzy("cats", 0, false), vec![1]);
+    }
+
+    #[test]
+    fn searchable_attributes_round_trips_through_setter() {
+        let mut index = InvertedIndex::default();
+        assert!(index.searchable_attributes().is_empty());
+
+        index.set_searchable_attributes(vec!["title".to_string()]);
+        assert_eq!(index.searchable_attributes(), &["title".to_string()]);
+    }
+
+    #[test]
+    fn filter_searchable_fields_indexes_everything_when_allowlist_is_empty() {
+        let index = InvertedIndex::default();
+        let fields: HashMap<String, String> = [
+            ("title".to_string(), "rust".to_string()),
+            ("body".to_string(), "search engine".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(index.filter_searchable_fields(&fields), "search engine rust");
+    }
+
+    #[test]
+    fn filter_searchable_fields_excludes_non_searchable_fields_once_allowlist_is_set() {
+        let mut index = InvertedIndex::default();
+        index.set_searchable_attributes(vec!["title".to_string()]);
+
+        let fields: HashMap<String, String> = [
+            ("title".to_string(), "rust".to_string()),
+            ("body".to_string(), "search engine".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(index.filter_searchable_fields(&fields), "rust");
+    }
+
+    #[test]
+    fn set_normalizer_updates_tokenize_at_runtime() {
+        let mut index = InvertedIndex::default();
+        assert_eq!(index.tokenize("cats"), vec![("cats".to_string(), 0)]);
+
+        index.set_normalizer(|token| token.trim_end_matches('s').to_string());
+        assert_eq!(index.tokenize("cats"), vec![("cat".to_string(), 0)]);
+    }
+
+    #[test]
+    fn set_normalizer_preserves_previously_configured_stop_words() {
+        let mut index = InvertedIndex::default();
+        index.set_stop_words(["the".to_string()].into_iter().collect());
+
+        index.set_normalizer(|token| token.trim_end_matches('s').to_string());
+
+        assert_eq!(index.tokenize("the cats"), vec![("cat".to_string(), 1)]);
+    }
 }
@@ -0,0 +1,4 @@
+pub mod doc_set;
+pub mod inverted_index;
+pub mod query;
+pub mod varint;
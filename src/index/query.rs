@@ -0,0 +1,206 @@
+/// A parsed search query, as a tree of boolean/phrase operations over terms.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Operation {
+    And(Vec<Operation>),
+    Or(Vec<Operation>),
+    Phrase(Vec<String>),
+    Term(String),
+}
+
+/// A single lexical unit produced while scanning a raw query string.
+enum Token {
+    Word(String),
+    Phrase(Vec<String>),
+    Or,
+}
+
+/// Parse a raw query string into an `Operation` tree.
+///
+/// Grammar: whitespace-separated words are ANDed together, `"..."` groups a
+/// phrase, and the literal word `OR` (case-insensitive) splits its
+/// surrounding words/phrases into alternatives.
+pub fn parse(query: &str) -> Operation {
+    let tokens = tokenize(query);
+
+    let mut or_groups: Vec<Vec<Token>> = vec![Vec::new()];
+    for token in tokens {
+        match token {
+            Token::Or => or_groups.push(Vec::new()),
+            other => or_groups.last_mut().unwrap().push(other),
+        }
+    }
+
+    let mut operations: Vec<Operation> = or_groups
+        .into_iter()
+        .map(and_group_to_operation)
+        .filter(|op| !is_empty_and(op))
+        .collect();
+
+    match operations.len() {
+        0 => Operation::And(Vec::new()),
+        1 => operations.remove(0),
+        _ => Operation::Or(operations),
+    }
+}
+
+fn and_group_to_operation(group: Vec<Token>) -> Operation {
+    let mut operands: Vec<Operation> = Vec::new();
+    for token in group {
+        match token {
+            Token::Word(word) => operands.push(Operation::Term(word)),
+            Token::Phrase(words) => operands.push(Operation::Phrase(words)),
+            Token::Or => unreachable!("OR tokens are split out before this point"),
+        }
+    }
+
+    match operands.len() {
+        1 => operands.remove(0),
+        _ => Operation::And(operands),
+    }
+}
+
+fn is_empty_and(op: &Operation) -> bool {
+    matches!(op, Operation::And(operands) if operands.is_empty())
+}
+
+fn tokenize(query: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+    let mut word = String::new();
+
+    let flush_word = |word: &mut String, tokens: &mut Vec<Token>| {
+        if word.is_empty() {
+            return;
+        }
+        let taken = std::mem::take(word);
+        if taken.eq_ignore_ascii_case("or") {
+            tokens.push(Token::Or);
+        } else {
+            tokens.push(Token::Word(taken.to_lowercase()));
+        }
+    };
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            '"' => {
+                flush_word(&mut word, &mut tokens);
+                chars.next();
+                let mut phrase = String::new();
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    phrase.push(c);
+                }
+                let words: Vec<String> = phrase
+                    .split_whitespace()
+                    .map(|w| w.to_lowercase())
+                    .collect();
+                if !words.is_empty() {
+                    tokens.push(Token::Phrase(words));
+                }
+            }
+            c if c.is_whitespace() => {
+                flush_word(&mut word, &mut tokens);
+                chars.next();
+            }
+            _ => {
+                word.push(c);
+                chars.next();
+            }
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_term() {
+        assert_eq!(parse("rust"), Operation::Term("rust".to_string()));
+    }
+
+    #[test]
+    fn lowercases_terms() {
+        assert_eq!(parse("Rust"), Operation::Term("rust".to_string()));
+    }
+
+    #[test]
+    fn parses_implicit_and() {
+        assert_eq!(
+            parse("rust search"),
+            Operation::And(vec![
+                Operation::Term("rust".to_string()),
+                Operation::Term("search".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_quoted_phrase() {
+        assert_eq!(
+            parse("\"rust search\""),
+            Operation::Phrase(vec!["rust".to_string(), "search".to_string()])
+        );
+    }
+
+    #[test]
+    fn parses_explicit_or() {
+        assert_eq!(
+            parse("rust OR golang"),
+            Operation::Or(vec![
+                Operation::Term("rust".to_string()),
+                Operation::Term("golang".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_or_between_and_groups() {
+        assert_eq!(
+            parse("fast rust OR slow golang"),
+            Operation::Or(vec![
+                Operation::And(vec![
+                    Operation::Term("fast".to_string()),
+                    Operation::Term("rust".to_string()),
+                ]),
+                Operation::And(vec![
+                    Operation::Term("slow".to_string()),
+                    Operation::Term("golang".to_string()),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_phrase_combined_with_and() {
+        assert_eq!(
+            parse("\"rust search\" fast"),
+            Operation::And(vec![
+                Operation::Phrase(vec!["rust".to_string(), "search".to_string()]),
+                Operation::Term("fast".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn empty_query_parses_to_empty_and() {
+        assert_eq!(parse(""), Operation::And(Vec::new()));
+        assert_eq!(parse("   "), Operation::And(Vec::new()));
+    }
+
+    #[test]
+    fn or_keyword_is_case_insensitive() {
+        assert_eq!(
+            parse("rust or golang"),
+            Operation::Or(vec![
+                Operation::Term("rust".to_string()),
+                Operation::Term("golang".to_string()),
+            ])
+        );
+    }
+}
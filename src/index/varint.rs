@@ -0,0 +1,78 @@
+//! Unsigned LEB128 variable-byte integers, used to compactly persist
+//! posting lists and the doc-length table: small gaps take a single byte
+//! instead of a fixed 8.
+
+/// Append `value` to `buf` as an unsigned LEB128 varint.
+pub fn write(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// Read a varint from `buf` starting at `*pos`, advancing `*pos` past it.
+pub fn read(buf: &[u8], pos: &mut usize) -> u64 {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = buf[*pos];
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_small_and_large_values() {
+        for value in [0u64, 1, 127, 128, 300, 16384, u64::MAX] {
+            let mut buf = Vec::new();
+            write(&mut buf, value);
+            let mut pos = 0;
+            assert_eq!(read(&buf, &mut pos), value);
+            assert_eq!(pos, buf.len());
+        }
+    }
+
+    #[test]
+    fn small_values_take_one_byte() {
+        let mut buf = Vec::new();
+        write(&mut buf, 5);
+        assert_eq!(buf.len(), 1);
+    }
+
+    #[test]
+    fn large_values_take_multiple_bytes() {
+        let mut buf = Vec::new();
+        write(&mut buf, 300);
+        assert!(buf.len() > 1);
+    }
+
+    #[test]
+    fn consecutive_values_read_back_in_order() {
+        let mut buf = Vec::new();
+        write(&mut buf, 1);
+        write(&mut buf, 2);
+        write(&mut buf, 300);
+
+        let mut pos = 0;
+        assert_eq!(read(&buf, &mut pos), 1);
+        assert_eq!(read(&buf, &mut pos), 2);
+        assert_eq!(read(&buf, &mut pos), 300);
+        assert_eq!(pos, buf.len());
+    }
+}
@@ -1,7 +1,14 @@
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::{Json, Router, extract::State, routing};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    routing,
+};
 use log::{Level, log};
 use serde::{Deserialize, Serialize};
 use tokio::{self, sync::Mutex};
@@ -10,15 +17,29 @@ mod index;
 
 use index::inverted_index::InvertedIndex;
 
+const INDEX_DIR: &str = "index_data";
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
 #[derive(Clone)]
 struct AppState {
     index: Arc<Mutex<InvertedIndex>>,
+    index_dir: PathBuf,
+}
+
+// Either a single block of text, or a map of named fields. When the index's
+// `searchable_attributes` is non-empty, only the listed fields are indexed;
+// an empty allowlist (the default) indexes every field.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum IndexContent {
+    Text(String),
+    Fields(HashMap<String, String>),
 }
 
 #[derive(Deserialize)]
 pub struct IndexRequest {
     pub doc_id: usize,
-    pub text: String,
+    pub text: IndexContent,
 }
 
 #[derive(Deserialize)]
@@ -26,6 +47,25 @@ pub struct SearchRequest {
     pub query: String,
 }
 
+#[derive(Deserialize)]
+pub struct TermSearchRequest {
+    pub term: String,
+}
+
+#[derive(Deserialize)]
+pub struct FuzzySearchRequest {
+    pub term: String,
+    pub max_distance: u8,
+    #[serde(default)]
+    pub prefix: bool,
+}
+
+#[derive(Deserialize)]
+pub struct SettingsRequest {
+    pub stop_words: Option<Vec<String>>,
+    pub searchable_attributes: Option<Vec<String>>,
+}
+
 #[derive(Serialize)]
 pub struct SearchResult {
     pub query: String,
@@ -34,17 +74,15 @@ pub struct SearchResult {
 
 // Index a document into the inverted index
 async fn index(State(state): State<AppState>, Json(req): Json<IndexRequest>) -> Json<SearchResult> {
-    let tokens: Vec<(String, usize)> = req
-        .text
-        .split_whitespace()
-        .enumerate()
-        .map(|(pos, term)| (term.to_lowercase(), pos))
-        .collect();
+    let mut idx = state.index.lock().await;
 
-    {
-        let mut idx = state.index.lock().await;
-        idx.add_document(req.doc_id, tokens);
-    }
+    let text = match req.text {
+        IndexContent::Text(text) => text,
+        IndexContent::Fields(fields) => idx.filter_searchable_fields(&fields),
+    };
+
+    let tokens = idx.tokenize(&text);
+    idx.add_document(req.doc_id, tokens);
 
     // For indexing we just return an empty hit list
     Json(SearchResult {
@@ -53,14 +91,33 @@ async fn index(State(state): State<AppState>, Json(req): Json<IndexRequest>) ->
     })
 }
 
-// Search the inverted index for a single term
+// Update stop words and/or searchable attributes at runtime
+async fn settings(
+    State(state): State<AppState>,
+    Json(req): Json<SettingsRequest>,
+) -> Json<SearchResult> {
+    let mut idx = state.index.lock().await;
+    if let Some(stop_words) = req.stop_words {
+        idx.set_stop_words(stop_words.into_iter().collect());
+    }
+    if let Some(searchable_attributes) = req.searchable_attributes {
+        idx.set_searchable_attributes(searchable_attributes);
+    }
+
+    Json(SearchResult {
+        query: String::from("settings updated"),
+        hits: Vec::new(),
+    })
+}
+
+// Search the inverted index with a boolean/phrase query (see index::query)
 async fn search(
     State(state): State<AppState>,
     Json(req): Json<SearchRequest>,
 ) -> Json<SearchResult> {
     let hits = {
         let idx = state.index.lock().await;
-        idx.rank(&req.query).unwrap_or_default()
+        idx.search(&req.query)
     };
 
     Json(SearchResult {
@@ -69,17 +126,96 @@ async fn search(
     })
 }
 
+// Rank documents containing a single exact term (see InvertedIndex::rank)
+async fn search_term(
+    State(state): State<AppState>,
+    Json(req): Json<TermSearchRequest>,
+) -> Json<SearchResult> {
+    let hits = {
+        let idx = state.index.lock().await;
+        idx.rank(&req.term).unwrap_or_default()
+    };
+
+    Json(SearchResult {
+        query: req.term,
+        hits,
+    })
+}
+
+// Typo-tolerant search: matches `term` and any vocabulary term within
+// `max_distance` edits (see InvertedIndex::rank_fuzzy)
+async fn search_fuzzy(
+    State(state): State<AppState>,
+    Json(req): Json<FuzzySearchRequest>,
+) -> Json<SearchResult> {
+    let hits = {
+        let idx = state.index.lock().await;
+        idx.rank_fuzzy(&req.term, req.max_distance, req.prefix)
+    };
+
+    Json(SearchResult {
+        query: req.term,
+        hits,
+    })
+}
+
+// Remove a document from the inverted index
+async fn delete(State(state): State<AppState>, Path(doc_id): Path<usize>) -> Json<SearchResult> {
+    {
+        let mut idx = state.index.lock().await;
+        idx.delete_document(doc_id);
+    }
+
+    Json(SearchResult {
+        query: String::from("deleted"),
+        hits: Vec::new(),
+    })
+}
+
+// Periodically flush the index to disk so a restart doesn't lose it.
+async fn flush_periodically(state: AppState) {
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let idx = state.index.lock().await;
+        if let Err(err) = idx.save(&state.index_dir) {
+            log!(Level::Error, "failed to flush index to {:?}: {err}", state.index_dir);
+        }
+    }
+}
+
+// Default token normalizer: strips leading/trailing punctuation so e.g.
+// "cats," and "cats" index and search identically. Not a real stemmer --
+// swap in one (e.g. via `InvertedIndex::set_normalizer`) for that.
+fn strip_punctuation(token: &str) -> String {
+    token.trim_matches(|c: char| !c.is_alphanumeric()).to_string()
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     env_logger::init();
 
+    let index_dir = PathBuf::from(INDEX_DIR);
+    let mut loaded_index = InvertedIndex::load(&index_dir).unwrap_or_else(|_| {
+        log!(Level::Info, "no persisted index found at {index_dir:?}, starting empty");
+        InvertedIndex::default()
+    });
+    loaded_index.set_normalizer(strip_punctuation);
+
     let state = AppState {
-        index: Arc::new(Mutex::new(InvertedIndex::default())),
+        index: Arc::new(Mutex::new(loaded_index)),
+        index_dir,
     };
 
+    tokio::spawn(flush_periodically(state.clone()));
+
     let app = Router::new()
         .route("/index/", routing::post(index))
+        .route("/index/{doc_id}", routing::delete(delete))
         .route("/search/", routing::post(search))
+        .route("/search/term/", routing::post(search_term))
+        .route("/search/fuzzy/", routing::post(search_fuzzy))
+        .route("/settings/", routing::put(settings))
         .with_state(state);
 
     let addr: SocketAddr = "0.0.0.0:3000".parse()?;